@@ -0,0 +1,539 @@
+use base64::{engine::general_purpose, Engine as _};
+use ring::{
+    digest, rand,
+    signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+const LETS_ENCRYPT_DIRECTORY_URL: &str = "https://acme-v02.api.letsencrypt.org/directory";
+
+/// Pending `http-01` challenge responses, keyed by token, served at
+/// `/.well-known/acme-challenge/{token}`.
+pub(crate) type ChallengeStore = Arc<Mutex<HashMap<String, String>>>;
+
+/// Configuration for the embedded ACME client.
+#[derive(Clone)]
+pub(crate) struct AcmeConfig {
+    pub domain: String,
+    pub contact_email: Option<String>,
+    pub directory_url: String,
+    pub account_key_path: PathBuf,
+    pub cert_path: PathBuf,
+    pub cert_key_path: PathBuf,
+}
+
+impl AcmeConfig {
+    pub fn from_env(domain: String) -> Self {
+        Self {
+            domain,
+            contact_email: std::env::var("ACME_CONTACT_EMAIL").ok(),
+            directory_url: std::env::var("ACME_DIRECTORY_URL")
+                .unwrap_or_else(|_| LETS_ENCRYPT_DIRECTORY_URL.to_string()),
+            account_key_path: PathBuf::from("acme_account_key.der"),
+            cert_path: PathBuf::from("acme_cert.pem"),
+            cert_key_path: PathBuf::from("acme_cert_key.der"),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum AcmeError {
+    #[error("ACME request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("ACME server returned an error: {0}")]
+    Server(String),
+    #[error("ACME signing error")]
+    Signing,
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("timed out waiting for order to become ready or valid")]
+    Timeout,
+}
+
+#[derive(Deserialize)]
+struct Directory {
+    #[serde(rename = "newNonce")]
+    new_nonce: String,
+    #[serde(rename = "newAccount")]
+    new_account: String,
+    #[serde(rename = "newOrder")]
+    new_order: String,
+}
+
+#[derive(Serialize)]
+struct JwsProtected<'a> {
+    alg: &'a str,
+    nonce: String,
+    url: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    jwk: Option<Jwk>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kid: Option<&'a str>,
+}
+
+#[derive(Serialize, Clone)]
+struct Jwk {
+    kty: &'static str,
+    crv: &'static str,
+    x: String,
+    y: String,
+}
+
+#[derive(Serialize)]
+struct JwsEnvelope {
+    protected: String,
+    payload: String,
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct NewOrderResponse {
+    status: String,
+    authorizations: Vec<String>,
+    finalize: String,
+}
+
+#[derive(Deserialize)]
+struct OrderStatusResponse {
+    status: String,
+    certificate: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AuthorizationResponse {
+    challenges: Vec<ChallengeResponse>,
+}
+
+#[derive(Deserialize, Clone)]
+struct ChallengeResponse {
+    #[serde(rename = "type")]
+    challenge_type: String,
+    url: String,
+    token: String,
+}
+
+/// A minimal ACME (RFC 8555) client driving the `http-01` order flow end to end.
+pub(crate) struct AcmeClient {
+    http: reqwest::Client,
+    directory: Directory,
+    account_key: EcdsaKeyPair,
+    jwk: Jwk,
+    kid: Option<String>,
+}
+
+fn b64url(bytes: &[u8]) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn load_or_create_account_key(path: &Path) -> Result<EcdsaKeyPair, AcmeError> {
+    let rng = rand::SystemRandom::new();
+
+    let pkcs8 = if path.exists() {
+        std::fs::read(path)?
+    } else {
+        let generated = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+            .map_err(|_| AcmeError::Signing)?;
+        std::fs::write(path, generated.as_ref())?;
+        generated.as_ref().to_vec()
+    };
+
+    EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &pkcs8, &rng)
+        .map_err(|_| AcmeError::Signing)
+}
+
+fn jwk_from_key(key: &EcdsaKeyPair) -> Jwk {
+    // Uncompressed SEC1 point: 0x04 || X (32 bytes) || Y (32 bytes).
+    let point = key.public_key().as_ref();
+    let x = &point[1..33];
+    let y = &point[33..65];
+    Jwk {
+        kty: "EC",
+        crv: "P-256",
+        x: b64url(x),
+        y: b64url(y),
+    }
+}
+
+fn jwk_thumbprint(jwk: &Jwk) -> String {
+    // RFC 7638: lexicographically ordered member names, no whitespace.
+    let canonical = format!(
+        r#"{{"crv":"{}","kty":"{}","x":"{}","y":"{}"}}"#,
+        jwk.crv, jwk.kty, jwk.x, jwk.y
+    );
+    b64url(digest::digest(&digest::SHA256, canonical.as_bytes()).as_ref())
+}
+
+impl AcmeClient {
+    pub async fn new(config: &AcmeConfig) -> Result<Self, AcmeError> {
+        let http = reqwest::Client::new();
+        let directory = http
+            .get(&config.directory_url)
+            .send()
+            .await?
+            .json::<Directory>()
+            .await?;
+
+        let account_key = load_or_create_account_key(&config.account_key_path)?;
+        let jwk = jwk_from_key(&account_key);
+
+        Ok(Self {
+            http,
+            directory,
+            account_key,
+            jwk,
+            kid: None,
+        })
+    }
+
+    async fn fetch_nonce(&self) -> Result<String, AcmeError> {
+        let response = self.http.head(&self.directory.new_nonce).send().await?;
+        response
+            .headers()
+            .get("replay-nonce")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+            .ok_or_else(|| AcmeError::Server("missing replay-nonce header".to_string()))
+    }
+
+    fn sign(&self, protected: &JwsProtected, payload: &str) -> Result<JwsEnvelope, AcmeError> {
+        let protected_b64 =
+            b64url(&serde_json::to_vec(protected).map_err(|_| AcmeError::Signing)?);
+        let signing_input = format!("{}.{}", protected_b64, payload);
+
+        let rng = rand::SystemRandom::new();
+        let signature = self
+            .account_key
+            .sign(&rng, signing_input.as_bytes())
+            .map_err(|_| AcmeError::Signing)?;
+
+        Ok(JwsEnvelope {
+            protected: protected_b64,
+            payload: payload.to_string(),
+            signature: b64url(signature.as_ref()),
+        })
+    }
+
+    async fn post_jws<T: for<'de> Deserialize<'de>>(
+        &self,
+        url: &str,
+        payload: &str,
+    ) -> Result<(T, reqwest::header::HeaderMap), AcmeError> {
+        let nonce = self.fetch_nonce().await?;
+        let protected = JwsProtected {
+            alg: "ES256",
+            nonce,
+            url,
+            jwk: self.kid.is_none().then(|| self.jwk.clone()),
+            kid: self.kid.as_deref(),
+        };
+        let envelope = self.sign(&protected, payload)?;
+
+        let response = self
+            .http
+            .post(url)
+            .header("content-type", "application/jose+json")
+            .json(&envelope)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(AcmeError::Server(body));
+        }
+
+        let headers = response.headers().clone();
+        let body = response.json::<T>().await?;
+        Ok((body, headers))
+    }
+
+    async fn ensure_account(&mut self, config: &AcmeConfig) -> Result<(), AcmeError> {
+        #[derive(Serialize)]
+        struct NewAccountPayload {
+            #[serde(rename = "termsOfServiceAgreed")]
+            terms_of_service_agreed: bool,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            contact: Option<Vec<String>>,
+        }
+
+        let payload = NewAccountPayload {
+            terms_of_service_agreed: true,
+            contact: config
+                .contact_email
+                .as_ref()
+                .map(|email| vec![format!("mailto:{email}")]),
+        };
+        let payload_json = serde_json::to_string(&payload).map_err(|_| AcmeError::Signing)?;
+
+        let (_, headers): (serde_json::Value, _) = self
+            .post_jws(&self.directory.new_account.clone(), &payload_json)
+            .await?;
+
+        self.kid = headers
+            .get("location")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+
+        Ok(())
+    }
+
+    /// Runs the full order -> challenge -> finalize flow for `config.domain`, serving the
+    /// `http-01` response token via `challenges`, and returns the PEM certificate chain plus
+    /// the matching private key bytes (PKCS#8 DER) that should be hot-loaded into the TLS config.
+    pub async fn obtain_certificate(
+        &mut self,
+        config: &AcmeConfig,
+        challenges: ChallengeStore,
+    ) -> Result<(String, Vec<u8>), AcmeError> {
+        self.ensure_account(config).await?;
+
+        #[derive(Serialize)]
+        struct Identifier<'a> {
+            #[serde(rename = "type")]
+            kind: &'a str,
+            value: &'a str,
+        }
+
+        #[derive(Serialize)]
+        struct NewOrderPayload<'a> {
+            identifiers: Vec<Identifier<'a>>,
+        }
+
+        let order_payload = NewOrderPayload {
+            identifiers: vec![Identifier {
+                kind: "dns",
+                value: &config.domain,
+            }],
+        };
+        let order_payload_json =
+            serde_json::to_string(&order_payload).map_err(|_| AcmeError::Signing)?;
+
+        let (order, order_headers): (NewOrderResponse, _) = self
+            .post_jws(&self.directory.new_order.clone(), &order_payload_json)
+            .await?;
+        let order_url = order_headers
+            .get("location")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| AcmeError::Server("order response missing location".to_string()))?
+            .to_string();
+
+        for authorization_url in &order.authorizations {
+            self.answer_http01(authorization_url, &challenges).await?;
+        }
+
+        self.poll_order_ready(&order_url).await?;
+
+        let csr_der = self.generate_csr(config)?;
+        let csr_b64 = b64url(&csr_der);
+
+        #[derive(Serialize)]
+        struct FinalizePayload {
+            csr: String,
+        }
+        let finalize_payload_json = serde_json::to_string(&FinalizePayload { csr: csr_b64 })
+            .map_err(|_| AcmeError::Signing)?;
+
+        let (_, _): (serde_json::Value, _) = self
+            .post_jws(&order.finalize, &finalize_payload_json)
+            .await?;
+
+        let certificate_url = self.poll_order_valid(&order_url).await?;
+        let (certificate_pem, _): (String, _) = self.post_jws_raw(&certificate_url).await?;
+
+        let key_pair_pkcs8 = std::fs::read(&config.cert_key_path)?;
+        Ok((certificate_pem, key_pair_pkcs8))
+    }
+
+    async fn answer_http01(
+        &self,
+        authorization_url: &str,
+        challenges: &ChallengeStore,
+    ) -> Result<(), AcmeError> {
+        let (authorization, _): (AuthorizationResponse, _) =
+            self.post_jws(authorization_url, "").await?;
+
+        let challenge = authorization
+            .challenges
+            .iter()
+            .find(|c| c.challenge_type == "http-01")
+            .ok_or_else(|| AcmeError::Server("no http-01 challenge offered".to_string()))?
+            .clone();
+
+        let key_authorization = format!("{}.{}", challenge.token, jwk_thumbprint(&self.jwk));
+        challenges
+            .lock()
+            .expect("challenge store mutex poisoned")
+            .insert(challenge.token.clone(), key_authorization);
+
+        let (_, _): (serde_json::Value, _) = self.post_jws(&challenge.url, "{}").await?;
+        Ok(())
+    }
+
+    /// Polls the order until all its authorizations are satisfied (`ready`), the only state in
+    /// which the server accepts the finalize request.
+    async fn poll_order_ready(&self, order_url: &str) -> Result<(), AcmeError> {
+        for _ in 0..20 {
+            let (status, _): (OrderStatusResponse, _) = self.post_jws(order_url, "").await?;
+            if status.status == "ready" {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+        Err(AcmeError::Timeout)
+    }
+
+    async fn poll_order_valid(&self, order_url: &str) -> Result<String, AcmeError> {
+        for _ in 0..20 {
+            let (status, _): (OrderStatusResponse, _) = self.post_jws(order_url, "").await?;
+            if status.status == "valid" {
+                return status
+                    .certificate
+                    .ok_or_else(|| AcmeError::Server("valid order missing certificate url".to_string()));
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+        Err(AcmeError::Timeout)
+    }
+
+    async fn post_jws_raw(&self, url: &str) -> Result<(String, reqwest::header::HeaderMap), AcmeError> {
+        let nonce = self.fetch_nonce().await?;
+        let protected = JwsProtected {
+            alg: "ES256",
+            nonce,
+            url,
+            jwk: None,
+            kid: self.kid.as_deref(),
+        };
+        let envelope = self.sign(&protected, "")?;
+
+        let response = self
+            .http
+            .post(url)
+            .header("content-type", "application/jose+json")
+            .json(&envelope)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(AcmeError::Server(body));
+        }
+
+        let headers = response.headers().clone();
+        let body = response.text().await?;
+        Ok((body, headers))
+    }
+
+    fn generate_csr(&self, config: &AcmeConfig) -> Result<Vec<u8>, AcmeError> {
+        // The certificate's own keypair is distinct from the ACME account keypair. It's
+        // persisted to `cert_key_path` *before* the CSR is built from it, so the key the CA
+        // signs over is the same one `provision_tls` later hot-loads alongside the cert.
+        let rng = rand::SystemRandom::new();
+        let cert_key = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+            .map_err(|_| AcmeError::Signing)?;
+        std::fs::write(&config.cert_key_path, cert_key.as_ref())?;
+
+        let key_pair =
+            rcgen::KeyPair::from_der(cert_key.as_ref()).map_err(|_| AcmeError::Signing)?;
+        let mut params = rcgen::CertificateParams::new(vec![config.domain.clone()]);
+        params.alg = &rcgen::PKCS_ECDSA_P256_SHA256;
+        params.key_pair = Some(key_pair);
+
+        let cert = rcgen::Certificate::from_params(params).map_err(|_| AcmeError::Signing)?;
+        cert.serialize_request_der().map_err(|_| AcmeError::Signing)
+    }
+}
+
+/// How long before a certificate's `notAfter` we kick off renewal.
+const RENEWAL_WINDOW: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Reads `cert_path` and returns how long to sleep before renewal is due. Any parse failure
+/// (missing file, malformed PEM) falls back to a short retry so a bad cert doesn't wedge the
+/// renewal loop forever.
+fn renewal_delay(cert_path: &Path) -> Duration {
+    let delay = (|| -> Result<Duration, AcmeError> {
+        let pem_bytes = std::fs::read(cert_path)?;
+        let (_, pem) =
+            x509_parser::pem::parse_x509_pem(&pem_bytes).map_err(|_| AcmeError::Signing)?;
+        let cert = pem.parse_x509().map_err(|_| AcmeError::Signing)?;
+
+        let not_after = cert.validity().not_after.timestamp();
+        let renew_at = not_after - RENEWAL_WINDOW.as_secs() as i64;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|_| AcmeError::Signing)?
+            .as_secs() as i64;
+
+        Ok(Duration::from_secs((renew_at - now).max(0) as u64))
+    })();
+
+    delay.unwrap_or(Duration::from_secs(60 * 60))
+}
+
+/// Background task that re-runs the ACME order flow once the current certificate enters its
+/// renewal window, then sleeps until the next one is due. Spawned once from `provision_tls`.
+///
+/// `tls_config` is the very `RustlsConfig` handed to `axum_server::bind_rustls` — it doesn't
+/// watch the filesystem on its own, so each successful renewal must explicitly reload it or the
+/// running process keeps serving the old certificate out of memory until restarted.
+fn spawn_renewal_task(
+    config: AcmeConfig,
+    challenges: ChallengeStore,
+    tls_config: axum_server::tls_rustls::RustlsConfig,
+) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(renewal_delay(&config.cert_path)).await;
+
+            let mut client = match AcmeClient::new(&config).await {
+                Ok(client) => client,
+                Err(_) => continue,
+            };
+
+            match client.obtain_certificate(&config, challenges.clone()).await {
+                Ok((certificate_pem, _)) => {
+                    if let Err(e) = std::fs::write(&config.cert_path, certificate_pem) {
+                        eprintln!("acme: failed to persist renewed certificate: {e}");
+                        continue;
+                    }
+                    if let Err(e) = tls_config
+                        .reload_from_pem_file(&config.cert_path, &config.cert_key_path)
+                        .await
+                    {
+                        eprintln!("acme: failed to reload renewed certificate: {e}");
+                    }
+                }
+                Err(e) => eprintln!("acme: certificate renewal failed: {e}"),
+            }
+        }
+    });
+}
+
+/// Obtains (or, on restart, reuses) a certificate for `config.domain` and returns a
+/// `RustlsConfig` ready for `axum_server::bind_rustls`. Schedules background renewal a week
+/// before the certificate's notAfter date, reloading the same `RustlsConfig` in place once a
+/// renewal succeeds.
+pub(crate) async fn provision_tls(
+    config: AcmeConfig,
+    challenges: ChallengeStore,
+) -> Result<axum_server::tls_rustls::RustlsConfig, AcmeError> {
+    if !config.cert_path.exists() || !config.cert_key_path.exists() {
+        let mut client = AcmeClient::new(&config).await?;
+        let (certificate_pem, _key_der) = client.obtain_certificate(&config, challenges.clone()).await?;
+        std::fs::write(&config.cert_path, certificate_pem)?;
+    }
+
+    let tls_config =
+        axum_server::tls_rustls::RustlsConfig::from_pem_file(&config.cert_path, &config.cert_key_path)
+            .await
+            .map_err(AcmeError::Io)?;
+
+    spawn_renewal_task(config, challenges, tls_config.clone());
+
+    Ok(tls_config)
+}
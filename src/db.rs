@@ -0,0 +1,206 @@
+use bb8::Pool;
+use bb8_postgres::PostgresConnectionManager;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio_postgres::NoTls;
+
+#[derive(Error, Debug)]
+pub(crate) enum DbError {
+    #[error("database pool error: {0}")]
+    Pool(#[from] bb8::RunError<tokio_postgres::Error>),
+    #[error("database query error: {0}")]
+    Query(#[from] tokio_postgres::Error),
+}
+
+const INIT_SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS keypairs (
+    pubkey TEXT PRIMARY KEY,
+    encrypted_secret BYTEA,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS signed_messages (
+    id BIGSERIAL PRIMARY KEY,
+    pubkey TEXT NOT NULL,
+    message_hash TEXT NOT NULL,
+    signature TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    key TEXT PRIMARY KEY,
+    request_hash TEXT NOT NULL,
+    response_body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+"#;
+
+#[derive(Serialize)]
+pub(crate) struct SignatureRecord {
+    pubkey: String,
+    message_hash: String,
+    signature: String,
+    created_at: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct KeypairRecord {
+    pubkey: String,
+    has_encrypted_secret: bool,
+    created_at: String,
+}
+
+/// A pooled Postgres handle. Queries are run by handing the pool to a closure (`execute_inline`)
+/// rather than exposing a long-lived connection, mirroring how `RpcClient` hands out a fresh
+/// client per call instead of holding cluster state.
+#[derive(Clone)]
+pub(crate) struct Db {
+    pool: Pool<PostgresConnectionManager<NoTls>>,
+}
+
+impl Db {
+    pub async fn connect(database_url: &str) -> Result<Self, DbError> {
+        let manager = PostgresConnectionManager::new_from_stringlike(database_url, NoTls)
+            .map_err(DbError::Query)?;
+        let pool = Pool::builder().build(manager).await?;
+
+        let connection = pool.get().await?;
+        connection.batch_execute(INIT_SCHEMA).await?;
+
+        Ok(Self { pool })
+    }
+
+    pub async fn execute_inline<F, Fut, T>(&self, f: F) -> Result<T, DbError>
+    where
+        F: FnOnce(Pool<PostgresConnectionManager<NoTls>>) -> Fut,
+        Fut: std::future::Future<Output = Result<T, DbError>>,
+    {
+        f(self.pool.clone()).await
+    }
+
+    pub async fn store_keypair(
+        &self,
+        pubkey: &str,
+        encrypted_secret: Option<&[u8]>,
+    ) -> Result<(), DbError> {
+        self.execute_inline(|pool| async move {
+            let connection = pool.get().await?;
+            connection
+                .execute(
+                    "INSERT INTO keypairs (pubkey, encrypted_secret) VALUES ($1, $2)
+                     ON CONFLICT (pubkey) DO NOTHING",
+                    &[&pubkey, &encrypted_secret],
+                )
+                .await?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn lookup_keypair(&self, pubkey: &str) -> Result<Option<KeypairRecord>, DbError> {
+        self.execute_inline(|pool| async move {
+            let connection = pool.get().await?;
+            let row = connection
+                .query_opt(
+                    "SELECT pubkey, encrypted_secret IS NOT NULL, created_at FROM keypairs WHERE pubkey = $1",
+                    &[&pubkey],
+                )
+                .await?;
+
+            Ok(row.map(|row| {
+                let created_at: chrono::DateTime<chrono::Utc> = row.get(2);
+                KeypairRecord {
+                    pubkey: row.get(0),
+                    has_encrypted_secret: row.get(1),
+                    created_at: created_at.to_rfc3339(),
+                }
+            }))
+        })
+        .await
+    }
+
+    pub async fn record_signature(
+        &self,
+        pubkey: &str,
+        message_hash: &str,
+        signature: &str,
+    ) -> Result<(), DbError> {
+        self.execute_inline(|pool| async move {
+            let connection = pool.get().await?;
+            connection
+                .execute(
+                    "INSERT INTO signed_messages (pubkey, message_hash, signature) VALUES ($1, $2, $3)",
+                    &[&pubkey, &message_hash, &signature],
+                )
+                .await?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn list_signatures(&self) -> Result<Vec<SignatureRecord>, DbError> {
+        self.execute_inline(|pool| async move {
+            let connection = pool.get().await?;
+            let rows = connection
+                .query(
+                    "SELECT pubkey, message_hash, signature, created_at
+                     FROM signed_messages ORDER BY created_at DESC LIMIT 100",
+                    &[],
+                )
+                .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|row| {
+                    let created_at: chrono::DateTime<chrono::Utc> = row.get(3);
+                    SignatureRecord {
+                        pubkey: row.get(0),
+                        message_hash: row.get(1),
+                        signature: row.get(2),
+                        created_at: created_at.to_rfc3339(),
+                    }
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Returns the stored request hash alongside the cached response body, so callers can tell
+    /// a true replay (same key, same body) from a key reused with a different request.
+    pub async fn get_idempotent_response(
+        &self,
+        key: &str,
+    ) -> Result<Option<(String, String)>, DbError> {
+        self.execute_inline(|pool| async move {
+            let connection = pool.get().await?;
+            let row = connection
+                .query_opt(
+                    "SELECT request_hash, response_body FROM idempotency_keys WHERE key = $1",
+                    &[&key],
+                )
+                .await?;
+            Ok(row.map(|row| (row.get(0), row.get(1))))
+        })
+        .await
+    }
+
+    pub async fn store_idempotent_response(
+        &self,
+        key: &str,
+        request_hash: &str,
+        response_body: &str,
+    ) -> Result<(), DbError> {
+        self.execute_inline(|pool| async move {
+            let connection = pool.get().await?;
+            connection
+                .execute(
+                    "INSERT INTO idempotency_keys (key, request_hash, response_body) VALUES ($1, $2, $3)
+                     ON CONFLICT (key) DO NOTHING",
+                    &[&key, &request_hash, &response_body],
+                )
+                .await?;
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,160 @@
+use axum::Json;
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+};
+use std::str::FromStr;
+
+use crate::{decode_signer_secret, ApiError, SuccessResponse};
+
+/// An Ed25519 key serialized as a JOSE JWK (RFC 8037, OKP / `Ed25519`).
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct Jwk {
+    kty: String,
+    crv: String,
+    x: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    d: Option<String>,
+}
+
+pub(crate) fn jwk_from_keypair(keypair: &Keypair, include_private: bool) -> Jwk {
+    let bytes = keypair.to_bytes();
+    let secret = &bytes[..32];
+    let public = &bytes[32..];
+
+    Jwk {
+        kty: "OKP".to_string(),
+        crv: "Ed25519".to_string(),
+        x: general_purpose::URL_SAFE_NO_PAD.encode(public),
+        d: include_private.then(|| general_purpose::URL_SAFE_NO_PAD.encode(secret)),
+    }
+}
+
+fn pubkey_from_jwk(jwk: &Jwk) -> Result<Pubkey, ApiError> {
+    if jwk.kty != "OKP" || jwk.crv != "Ed25519" {
+        return Err(ApiError::InvalidPubkey(
+            "JWK must be an OKP key with crv=Ed25519".to_string(),
+        ));
+    }
+    let public_bytes = general_purpose::URL_SAFE_NO_PAD
+        .decode(&jwk.x)
+        .map_err(|_| ApiError::InvalidPubkey("invalid JWK x value".to_string()))?;
+
+    Pubkey::try_from(public_bytes.as_slice())
+        .map_err(|_| ApiError::InvalidPubkey("invalid JWK x value".to_string()))
+}
+
+#[derive(Serialize)]
+struct JwsHeader<'a> {
+    alg: &'a str,
+    typ: &'a str,
+}
+
+fn jws_header_b64() -> String {
+    let header = JwsHeader {
+        alg: "EdDSA",
+        typ: "JWT",
+    };
+    general_purpose::URL_SAFE_NO_PAD.encode(
+        serde_json::to_vec(&header).expect("JwsHeader always serializes"),
+    )
+}
+
+#[derive(Deserialize)]
+pub(crate) struct JwsSignRequest {
+    secret: String,
+    payload: String,
+    /// When true, the payload segment is omitted from the compact serialization (RFC 7797);
+    /// the verifier must supply the original payload out of band.
+    detached: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct JwsSignResponse {
+    jws: String,
+}
+
+pub(crate) async fn sign(
+    Json(payload): Json<JwsSignRequest>,
+) -> Result<Json<SuccessResponse<JwsSignResponse>>, ApiError> {
+    let keypair = decode_signer_secret(&payload.secret)?;
+
+    let header_b64 = jws_header_b64();
+    let payload_b64 = general_purpose::URL_SAFE_NO_PAD.encode(payload.payload.as_bytes());
+    let signing_input = format!("{}.{}", header_b64, payload_b64);
+
+    let signature = keypair.sign_message(signing_input.as_bytes());
+    let signature_b64 = general_purpose::URL_SAFE_NO_PAD.encode(signature.as_ref());
+
+    let payload_segment = if payload.detached.unwrap_or(false) {
+        String::new()
+    } else {
+        payload_b64
+    };
+
+    let jws = format!("{}.{}.{}", header_b64, payload_segment, signature_b64);
+
+    Ok(Json(SuccessResponse::new(JwsSignResponse { jws })))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct JwsVerifyRequest {
+    jws: String,
+    /// Required when `jws` was produced with `detached: true`.
+    payload: Option<String>,
+    jwk: Option<Jwk>,
+    pubkey: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct JwsVerifyResponse {
+    valid: bool,
+}
+
+pub(crate) async fn verify(
+    Json(payload): Json<JwsVerifyRequest>,
+) -> Result<Json<SuccessResponse<JwsVerifyResponse>>, ApiError> {
+    let mut segments = payload.jws.splitn(3, '.');
+    let header_b64 = segments
+        .next()
+        .ok_or_else(|| ApiError::InvalidSignature("malformed JWS".to_string()))?;
+    let payload_segment = segments
+        .next()
+        .ok_or_else(|| ApiError::InvalidSignature("malformed JWS".to_string()))?;
+    let signature_b64 = segments
+        .next()
+        .ok_or_else(|| ApiError::InvalidSignature("malformed JWS".to_string()))?;
+
+    let payload_b64 = if payload_segment.is_empty() {
+        let detached_payload = payload
+            .payload
+            .as_ref()
+            .ok_or_else(|| ApiError::MissingField("payload".to_string()))?;
+        general_purpose::URL_SAFE_NO_PAD.encode(detached_payload.as_bytes())
+    } else {
+        payload_segment.to_string()
+    };
+
+    let signing_input = format!("{}.{}", header_b64, payload_b64);
+
+    let signature_bytes = general_purpose::URL_SAFE_NO_PAD
+        .decode(signature_b64)
+        .map_err(|_| ApiError::InvalidSignature("invalid signature encoding".to_string()))?;
+    let signature = Signature::try_from(signature_bytes.as_slice())
+        .map_err(|_| ApiError::InvalidSignature("invalid signature bytes".to_string()))?;
+
+    let pubkey = match (payload.jwk, payload.pubkey) {
+        (Some(jwk), _) => pubkey_from_jwk(&jwk)?,
+        (None, Some(pubkey)) => Pubkey::from_str(&pubkey)
+            .map_err(|_| ApiError::InvalidPubkey(pubkey.clone()))?,
+        (None, None) => {
+            return Err(ApiError::MissingField("jwk or pubkey".to_string()));
+        }
+    };
+
+    let valid = signature.verify(pubkey.as_ref(), signing_input.as_bytes());
+
+    Ok(Json(SuccessResponse::new(JwsVerifyResponse { valid })))
+}
@@ -17,8 +17,15 @@ use solana_sdk::program_error;
 use axum::response::IntoResponse;
 use axum::http::StatusCode;
 
+mod acme;
+mod db;
+mod jose;
+mod nft;
+mod rpc;
+mod tx_builder;
+
 #[derive(Error, Debug)]
-enum ApiError {
+pub(crate) enum ApiError {
     #[error("Invalid public key: {0}")]
     InvalidPubkey(String),
     #[error("Invalid secret key: {0}")]
@@ -31,6 +38,10 @@ enum ApiError {
     InvalidAmount,
     #[error("Program error: {0}")]
     ProgramError(String),
+    #[error("RPC error: {0}")]
+    RpcError(String),
+    #[error("Idempotency-Key '{0}' was already used with a different request body")]
+    IdempotencyKeyConflict(String),
 }
 
 impl From<program_error::ProgramError> for ApiError {
@@ -39,6 +50,66 @@ impl From<program_error::ProgramError> for ApiError {
     }
 }
 
+impl From<db::DbError> for ApiError {
+    fn from(err: db::DbError) -> Self {
+        ApiError::ProgramError(err.to_string())
+    }
+}
+
+/// Shared axum state: the ACME challenge store is always present, the Postgres pool is only
+/// populated when `DATABASE_URL` is configured, so persistence and idempotency stay opt-in.
+#[derive(Clone)]
+pub(crate) struct AppState {
+    challenges: acme::ChallengeStore,
+    db: Option<db::Db>,
+}
+
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Hashes a JSON-serializable request body so it can be compared against what an `Idempotency-Key`
+/// was originally stored against, rather than trusting the header alone.
+fn hash_request_body<T: Serialize>(request: &T) -> Result<String, ApiError> {
+    let body = serde_json::to_vec(request).map_err(|e| ApiError::ProgramError(e.to_string()))?;
+    Ok(solana_sdk::hash::hash(&body).to_string())
+}
+
+async fn load_idempotent_response<Req: Serialize, Res: serde::de::DeserializeOwned>(
+    state: &AppState,
+    headers: &axum::http::HeaderMap,
+    request: &Req,
+) -> Result<Option<Res>, ApiError> {
+    let (Some(db), Some(key)) = (&state.db, headers.get(IDEMPOTENCY_KEY_HEADER)) else {
+        return Ok(None);
+    };
+    let key = key.to_str().map_err(|_| ApiError::MissingField(IDEMPOTENCY_KEY_HEADER.to_string()))?;
+    let request_hash = hash_request_body(request)?;
+
+    match db.get_idempotent_response(key).await? {
+        Some((stored_hash, body)) if stored_hash == request_hash => Ok(Some(
+            serde_json::from_str(&body).map_err(|e| ApiError::ProgramError(e.to_string()))?,
+        )),
+        Some(_) => Err(ApiError::IdempotencyKeyConflict(key.to_string())),
+        None => Ok(None),
+    }
+}
+
+async fn store_idempotent_response<Req: Serialize, Res: Serialize>(
+    state: &AppState,
+    headers: &axum::http::HeaderMap,
+    request: &Req,
+    response: &Res,
+) -> Result<(), ApiError> {
+    let (Some(db), Some(key)) = (&state.db, headers.get(IDEMPOTENCY_KEY_HEADER)) else {
+        return Ok(());
+    };
+    let key = key.to_str().map_err(|_| ApiError::MissingField(IDEMPOTENCY_KEY_HEADER.to_string()))?;
+    let request_hash = hash_request_body(request)?;
+    let body = serde_json::to_string(response).map_err(|e| ApiError::ProgramError(e.to_string()))?;
+
+    db.store_idempotent_response(key, &request_hash, &body).await?;
+    Ok(())
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> axum::response::Response {
         let status_code = StatusCode::BAD_REQUEST;
@@ -50,8 +121,8 @@ impl IntoResponse for ApiError {
     }
 }
 
-#[derive(Serialize)]
-struct SuccessResponse<T> {
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SuccessResponse<T> {
     success: bool,
     data: T,
 }
@@ -65,43 +136,99 @@ impl<T> SuccessResponse<T> {
     }
 }
 
+/// Decodes a base58-encoded secret key, shared by every handler that accepts a signer secret
+/// (`sign_message`, the `/tx/*` broadcast and build routes, and the JWS signer).
+pub(crate) fn decode_signer_secret(secret: &str) -> Result<Keypair, ApiError> {
+    let secret_bytes = bs58::decode(secret)
+        .into_vec()
+        .map_err(|_| ApiError::InvalidSecretKey("Invalid base58 encoding".to_string()))?;
+
+    Keypair::from_bytes(&secret_bytes)
+        .map_err(|_| ApiError::InvalidSecretKey("Invalid keypair bytes".to_string()))
+}
+
 
 #[derive(Serialize)]
 struct KeypairResponse {
     pubkey: String,
     secret: String,
+    jwk: jose::Jwk,
 }
 
-async fn generate_keypair() -> Result<Json<SuccessResponse<KeypairResponse>>, ApiError> {
+async fn generate_keypair(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Json<SuccessResponse<KeypairResponse>>, ApiError> {
     let keypair = Keypair::new();
     let pubkey = keypair.pubkey().to_string();
     let secret = bs58::encode(keypair.to_bytes()).into_string();
+    let jwk = jose::jwk_from_keypair(&keypair, true);
 
-    Ok(Json(SuccessResponse::new(KeypairResponse { pubkey, secret })))
+    if let Some(db) = &state.db {
+        // Only the pubkey is persisted; the secret never leaves this response.
+        db.store_keypair(&pubkey, None).await?;
+    }
+
+    Ok(Json(SuccessResponse::new(KeypairResponse {
+        pubkey,
+        secret,
+        jwk,
+    })))
 }
 
 
 #[derive(Deserialize)]
-struct CreateTokenRequest {
+pub(crate) struct CreateTokenRequest {
     mint_authority: String,
     mint: String,
     decimals: u8,
 }
 
-#[derive(Serialize)]
-struct AccountInfo {
+#[derive(Serialize, Deserialize)]
+pub(crate) struct AccountInfo {
     pubkey: String,
     is_signer: bool,
     is_writable: bool,
 }
 
-#[derive(Serialize)]
-struct InstructionResponse {
+#[derive(Serialize, Deserialize)]
+pub(crate) struct InstructionResponse {
     program_id: String,
     accounts: Vec<AccountInfo>,
     instruction_data: String,
 }
 
+impl From<&solana_sdk::instruction::Instruction> for InstructionResponse {
+    fn from(instruction: &solana_sdk::instruction::Instruction) -> Self {
+        Self {
+            program_id: instruction.program_id.to_string(),
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|account_meta| AccountInfo {
+                    pubkey: account_meta.pubkey.to_string(),
+                    is_signer: account_meta.is_signer,
+                    is_writable: account_meta.is_writable,
+                })
+                .collect(),
+            instruction_data: general_purpose::STANDARD.encode(&instruction.data),
+        }
+    }
+}
+
+pub(crate) fn build_create_token_instruction(
+    mint_authority: &Pubkey,
+    mint: &Pubkey,
+    decimals: u8,
+) -> Result<solana_sdk::instruction::Instruction, ApiError> {
+    Ok(token_instruction::initialize_mint(
+        &spl_token::id(),
+        mint,
+        mint_authority,
+        None,
+        decimals,
+    )?)
+}
+
 async fn create_token(
     Json(payload): Json<CreateTokenRequest>,
 ) -> Result<Json<SuccessResponse<InstructionResponse>>, ApiError> {
@@ -110,43 +237,47 @@ async fn create_token(
     let mint = Pubkey::from_str(&payload.mint)
         .map_err(|_| ApiError::InvalidPubkey(payload.mint.clone()))?;
 
-    let instruction = token_instruction::initialize_mint(
-        &spl_token::id(),
-        &mint,
-        &mint_authority,
-        None,
-        payload.decimals,
-    )?;
-
-    let accounts = instruction
-        .accounts
-        .iter()
-        .map(|account_meta| AccountInfo {
-            pubkey: account_meta.pubkey.to_string(),
-            is_signer: account_meta.is_signer,
-            is_writable: account_meta.is_writable,
-        })
-        .collect();
-
-    Ok(Json(SuccessResponse::new(InstructionResponse {
-        program_id: instruction.program_id.to_string(),
-        accounts,
-        instruction_data: general_purpose::STANDARD.encode(instruction.data),
-    })))
+    let instruction = build_create_token_instruction(&mint_authority, &mint, payload.decimals)?;
+
+    Ok(Json(SuccessResponse::new(InstructionResponse::from(
+        &instruction,
+    ))))
 }
 
 
-#[derive(Deserialize)]
-struct MintTokenRequest {
+#[derive(Deserialize, Serialize)]
+pub(crate) struct MintTokenRequest {
     mint: String,
     destination: String,
     authority: String,
     amount: u64,
 }
 
+pub(crate) fn build_mint_token_instruction(
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Result<solana_sdk::instruction::Instruction, ApiError> {
+    Ok(token_instruction::mint_to(
+        &spl_token::id(),
+        mint,
+        destination,
+        authority,
+        &[],
+        amount,
+    )?)
+}
+
 async fn mint_token(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
     Json(payload): Json<MintTokenRequest>,
 ) -> Result<Json<SuccessResponse<InstructionResponse>>, ApiError> {
+    if let Some(cached) = load_idempotent_response(&state, &headers, &payload).await? {
+        return Ok(Json(cached));
+    }
+
     let mint = Pubkey::from_str(&payload.mint)
         .map_err(|_| ApiError::InvalidPubkey(payload.mint.clone()))?;
     let destination = Pubkey::from_str(&payload.destination)
@@ -154,30 +285,13 @@ async fn mint_token(
     let authority = Pubkey::from_str(&payload.authority)
         .map_err(|_| ApiError::InvalidPubkey(payload.authority.clone()))?;
 
-    let instruction = token_instruction::mint_to(
-        &spl_token::id(),
-        &mint,
-        &destination,
-        &authority,
-        &[],
-        payload.amount,
-    )?;
-
-    let accounts = instruction
-        .accounts
-        .iter()
-        .map(|account_meta| AccountInfo {
-            pubkey: account_meta.pubkey.to_string(),
-            is_signer: account_meta.is_signer,
-            is_writable: account_meta.is_writable,
-        })
-        .collect();
-
-    Ok(Json(SuccessResponse::new(InstructionResponse {
-        program_id: instruction.program_id.to_string(),
-        accounts,
-        instruction_data: general_purpose::STANDARD.encode(instruction.data),
-    })))
+    let instruction =
+        build_mint_token_instruction(&mint, &destination, &authority, payload.amount)?;
+    let response = SuccessResponse::new(InstructionResponse::from(&instruction));
+
+    store_idempotent_response(&state, &headers, &payload, &response).await?;
+
+    Ok(Json(response))
 }
 
 
@@ -195,6 +309,7 @@ struct SignMessageResponse {
 }
 
 async fn sign_message(
+    axum::extract::State(state): axum::extract::State<AppState>,
     Json(payload): Json<SignMessageRequest>,
 ) -> Result<Json<SuccessResponse<SignMessageResponse>>, ApiError> {
     if payload.message.is_empty() {
@@ -204,16 +319,17 @@ async fn sign_message(
         return Err(ApiError::MissingField("secret".to_string()));
     }
 
-    let secret_bytes = bs58::decode(&payload.secret)
-        .into_vec()
-        .map_err(|_| ApiError::InvalidSecretKey("Invalid base58 encoding".to_string()))?;
-
-    let keypair = Keypair::from_bytes(&secret_bytes)
-        .map_err(|_| ApiError::InvalidSecretKey("Invalid keypair bytes".to_string()))?;
+    let keypair = decode_signer_secret(&payload.secret)?;
 
     let signature = keypair.sign_message(payload.message.as_bytes());
     let signature_base64 = general_purpose::STANDARD.encode(signature.as_ref());
 
+    if let Some(db) = &state.db {
+        let message_hash = solana_sdk::hash::hash(payload.message.as_bytes()).to_string();
+        db.record_signature(&keypair.pubkey().to_string(), &message_hash, &signature_base64)
+            .await?;
+    }
+
     Ok(Json(SuccessResponse::new(SignMessageResponse {
         signature: signature_base64,
         public_key: keypair.pubkey().to_string(),
@@ -269,16 +385,22 @@ async fn verify_message(
 }
 
 
-#[derive(Deserialize)]
-struct SendSolRequest {
+#[derive(Deserialize, Serialize)]
+pub(crate) struct SendSolRequest {
     from: String,
     to: String,
     lamports: u64,
 }
 
 async fn send_sol(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
     Json(payload): Json<SendSolRequest>,
 ) -> Result<Json<SuccessResponse<InstructionResponse>>, ApiError> {
+    if let Some(cached) = load_idempotent_response(&state, &headers, &payload).await? {
+        return Ok(Json(cached));
+    }
+
     if payload.lamports == 0 {
         return Err(ApiError::InvalidAmount);
     }
@@ -289,33 +411,38 @@ async fn send_sol(
         .map_err(|_| ApiError::InvalidPubkey(payload.to.clone()))?;
 
     let instruction = system_instruction::transfer(&from, &to, payload.lamports);
+    let response = SuccessResponse::new(InstructionResponse::from(&instruction));
 
-    let accounts = instruction
-        .accounts
-        .iter()
-        .map(|account_meta| AccountInfo {
-            pubkey: account_meta.pubkey.to_string(),
-            is_signer: account_meta.is_signer,
-            is_writable: account_meta.is_writable,
-        })
-        .collect();
-
-    Ok(Json(SuccessResponse::new(InstructionResponse {
-        program_id: instruction.program_id.to_string(),
-        accounts,
-        instruction_data: general_purpose::STANDARD.encode(instruction.data),
-    })))
+    store_idempotent_response(&state, &headers, &payload, &response).await?;
+
+    Ok(Json(response))
 }
 
 
 #[derive(Deserialize)]
-struct SendTokenRequest {
+pub(crate) struct SendTokenRequest {
     destination: String,
     mint: String,
     owner: String,
     amount: u64,
 }
 
+pub(crate) fn build_send_token_instruction(
+    source: &Pubkey,
+    destination: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+) -> Result<solana_sdk::instruction::Instruction, ApiError> {
+    Ok(token_instruction::transfer(
+        &spl_token::id(),
+        source,
+        destination,
+        owner,
+        &[],
+        amount,
+    )?)
+}
+
 async fn send_token(
     Json(payload): Json<SendTokenRequest>,
 ) -> Result<Json<SuccessResponse<InstructionResponse>>, ApiError> {
@@ -331,50 +458,121 @@ async fn send_token(
         .map_err(|_| ApiError::InvalidPubkey(payload.owner.clone()))?;
 
     let source = owner;
-    let destination = destination;
 
-    let instruction = token_instruction::transfer(
-        &spl_token::id(),
-        &source,
-        &destination,
-        &owner,
-        &[],
-        payload.amount,
-    )?;
-
-    let accounts = instruction
-        .accounts
-        .iter()
-        .map(|account_meta| AccountInfo {
-            pubkey: account_meta.pubkey.to_string(),
-            is_signer: account_meta.is_signer,
-            is_writable: account_meta.is_writable,
-        })
-        .collect();
-
-    Ok(Json(SuccessResponse::new(InstructionResponse {
-        program_id: instruction.program_id.to_string(),
-        accounts,
-        instruction_data: general_purpose::STANDARD.encode(instruction.data),
-    })))
+    let instruction = build_send_token_instruction(&source, &destination, &owner, payload.amount)?;
+
+    Ok(Json(SuccessResponse::new(InstructionResponse::from(
+        &instruction,
+    ))))
+}
+
+async fn acme_challenge(
+    axum::extract::Path(token): axum::extract::Path<String>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<String, StatusCode> {
+    state
+        .challenges
+        .lock()
+        .expect("challenge store mutex poisoned")
+        .get(&token)
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn get_keypair(
+    axum::extract::Path(pubkey): axum::extract::Path<String>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Json<SuccessResponse<db::KeypairRecord>>, ApiError> {
+    let db = state
+        .db
+        .as_ref()
+        .ok_or_else(|| ApiError::ProgramError("persistence is not configured".to_string()))?;
+
+    let record = db
+        .lookup_keypair(&pubkey)
+        .await?
+        .ok_or_else(|| ApiError::InvalidPubkey(pubkey.clone()))?;
+
+    Ok(Json(SuccessResponse::new(record)))
+}
+
+async fn get_signature_history(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Json<SuccessResponse<Vec<db::SignatureRecord>>>, ApiError> {
+    let db = state
+        .db
+        .as_ref()
+        .ok_or_else(|| ApiError::ProgramError("persistence is not configured".to_string()))?;
+
+    Ok(Json(SuccessResponse::new(db.list_signatures().await?)))
 }
 
-fn create_router() -> Router {
+fn create_router(state: AppState) -> Router {
     Router::new()
         .route("/keypair", post(generate_keypair))
+        .route("/keypair/:pubkey", axum::routing::get(get_keypair))
         .route("/token/create", post(create_token))
         .route("/token/mint", post(mint_token))
         .route("/message/sign", post(sign_message))
         .route("/message/verify", post(verify_message))
         .route("/send/sol", post(send_sol))
         .route("/send/token", post(send_token))
+        .route("/tx/create_token", post(rpc::broadcast_create_token))
+        .route("/tx/mint_token", post(rpc::broadcast_mint_token))
+        .route("/tx/send_sol", post(rpc::broadcast_send_sol))
+        .route("/tx/send_token", post(rpc::broadcast_send_token))
+        .route("/airdrop", post(rpc::airdrop))
+        .route("/nft/create", post(nft::create_nft))
+        .route("/history/signatures", axum::routing::get(get_signature_history))
+        .route("/jws/sign", post(jose::sign))
+        .route("/jws/verify", post(jose::verify))
+        .route("/tx/build", post(tx_builder::build_transaction))
+        .route(
+            "/.well-known/acme-challenge/:token",
+            axum::routing::get(acme_challenge),
+        )
+        .with_state(state)
 }
 
 #[tokio::main]
 async fn main() {
-    let app = create_router();
-
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
-    println!("Server running on http://localhost:3000");
-    axum::serve(listener, app).await.unwrap();
+    let challenges: acme::ChallengeStore = Default::default();
+
+    let db = match std::env::var("DATABASE_URL") {
+        Ok(database_url) => Some(
+            db::Db::connect(&database_url)
+                .await
+                .expect("failed to connect to DATABASE_URL"),
+        ),
+        Err(_) => None,
+    };
+
+    let state = AppState {
+        challenges: challenges.clone(),
+        db,
+    };
+    let app = create_router(state);
+
+    let tls_domain = std::env::var("TLS_DOMAIN").ok();
+
+    match tls_domain {
+        Some(domain) => {
+            let config = acme::AcmeConfig::from_env(domain);
+            let tls_config = acme::provision_tls(config, challenges)
+                .await
+                .expect("failed to provision TLS certificate");
+
+            let addr: std::net::SocketAddr = "0.0.0.0:443".parse().unwrap();
+            println!("Server running on https://{addr}");
+            axum_server::bind_rustls(addr, tls_config)
+                .serve(app.into_make_service())
+                .await
+                .unwrap();
+        }
+        None => {
+            let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+            println!("Server running on http://localhost:3000");
+            axum::serve(listener, app).await.unwrap();
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,238 @@
+use axum::Json;
+use borsh::BorshSerialize;
+use serde::Deserialize;
+use solana_sdk::{pubkey::Pubkey, system_program, sysvar};
+use spl_associated_token_account::get_associated_token_address;
+use std::str::FromStr;
+
+use crate::{build_create_token_instruction, build_mint_token_instruction, ApiError, InstructionResponse, SuccessResponse};
+
+const TOKEN_METADATA_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+
+fn metadata_program_id() -> Pubkey {
+    Pubkey::from_str(TOKEN_METADATA_PROGRAM_ID).expect("valid hardcoded program id")
+}
+
+fn find_metadata_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    let program_id = metadata_program_id();
+    Pubkey::find_program_address(
+        &[b"metadata", program_id.as_ref(), mint.as_ref()],
+        &program_id,
+    )
+}
+
+fn find_master_edition_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    let program_id = metadata_program_id();
+    Pubkey::find_program_address(
+        &[b"metadata", program_id.as_ref(), mint.as_ref(), b"edition"],
+        &program_id,
+    )
+}
+
+#[derive(Deserialize)]
+pub(crate) struct NftCreator {
+    address: String,
+    verified: bool,
+    share: u8,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CreateNftRequest {
+    mint: String,
+    mint_authority: String,
+    payer: String,
+    owner: String,
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    creators: Option<Vec<NftCreator>>,
+}
+
+/// Builds a `create_metadata_accounts_v3` instruction for the Metaplex token-metadata program.
+///
+/// This crate doesn't depend on `mpl-token-metadata` directly, so the instruction is assembled
+/// by hand from the program's well-known accounts/data layout, mirroring the pattern the other
+/// handlers use for `spl_token`/`system_instruction`. The program deserializes instruction data
+/// with Borsh (not bincode) — `Vec`/`String` are length-prefixed with a `u32`, not a `u64` — so
+/// the args struct below derives `BorshSerialize` to match what's actually on-chain.
+fn build_create_metadata_instruction(
+    metadata: &Pubkey,
+    mint: &Pubkey,
+    mint_authority: &Pubkey,
+    payer: &Pubkey,
+    update_authority: &Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    creators: Option<Vec<NftCreator>>,
+) -> Result<solana_sdk::instruction::Instruction, ApiError> {
+    #[derive(BorshSerialize)]
+    struct CreatorData {
+        address: Pubkey,
+        verified: bool,
+        share: u8,
+    }
+
+    #[derive(BorshSerialize)]
+    struct DataV2 {
+        name: String,
+        symbol: String,
+        uri: String,
+        seller_fee_basis_points: u16,
+        creators: Option<Vec<CreatorData>>,
+        collection: Option<()>,
+        uses: Option<()>,
+    }
+
+    #[derive(BorshSerialize)]
+    struct CreateMetadataAccountArgsV3 {
+        data: DataV2,
+        is_mutable: bool,
+        collection_details: Option<()>,
+    }
+
+    let creators = creators
+        .map(|list| {
+            list.into_iter()
+                .map(|c| {
+                    Ok(CreatorData {
+                        address: Pubkey::from_str(&c.address)
+                            .map_err(|_| ApiError::InvalidPubkey(c.address))?,
+                        verified: c.verified,
+                        share: c.share,
+                    })
+                })
+                .collect::<Result<Vec<_>, ApiError>>()
+        })
+        .transpose()?;
+
+    let data = CreateMetadataAccountArgsV3 {
+        data: DataV2 {
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points,
+            creators,
+            collection: None,
+            uses: None,
+        },
+        is_mutable: true,
+        collection_details: None,
+    };
+
+    let mut instruction_data = vec![33u8]; // CreateMetadataAccountV3 discriminant
+    instruction_data.extend(
+        data.try_to_vec()
+            .map_err(|e| ApiError::ProgramError(e.to_string()))?,
+    );
+
+    Ok(solana_sdk::instruction::Instruction {
+        program_id: metadata_program_id(),
+        accounts: vec![
+            solana_sdk::instruction::AccountMeta::new(*metadata, false),
+            solana_sdk::instruction::AccountMeta::new_readonly(*mint, false),
+            solana_sdk::instruction::AccountMeta::new_readonly(*mint_authority, true),
+            solana_sdk::instruction::AccountMeta::new(*payer, true),
+            solana_sdk::instruction::AccountMeta::new_readonly(*update_authority, true),
+            solana_sdk::instruction::AccountMeta::new_readonly(system_program::id(), false),
+            solana_sdk::instruction::AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: instruction_data,
+    })
+}
+
+fn build_create_master_edition_instruction(
+    master_edition: &Pubkey,
+    mint: &Pubkey,
+    update_authority: &Pubkey,
+    mint_authority: &Pubkey,
+    payer: &Pubkey,
+    metadata: &Pubkey,
+) -> solana_sdk::instruction::Instruction {
+    solana_sdk::instruction::Instruction {
+        program_id: metadata_program_id(),
+        accounts: vec![
+            solana_sdk::instruction::AccountMeta::new(*master_edition, false),
+            solana_sdk::instruction::AccountMeta::new(*mint, false),
+            solana_sdk::instruction::AccountMeta::new_readonly(*update_authority, true),
+            solana_sdk::instruction::AccountMeta::new_readonly(*mint_authority, true),
+            solana_sdk::instruction::AccountMeta::new(*payer, true),
+            solana_sdk::instruction::AccountMeta::new(*metadata, false),
+            solana_sdk::instruction::AccountMeta::new_readonly(spl_token::id(), false),
+            solana_sdk::instruction::AccountMeta::new_readonly(system_program::id(), false),
+            solana_sdk::instruction::AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        // CreateMasterEditionV3 discriminant, followed by Borsh `Option<u64>` max_supply:
+        // 1u8 (Some tag) + 8 LE bytes of 0 => Some(0), a single-print (non-fungible) edition.
+        data: vec![17u8, 1u8, 0, 0, 0, 0, 0, 0, 0, 0],
+    }
+}
+
+pub(crate) async fn create_nft(
+    Json(payload): Json<CreateNftRequest>,
+) -> Result<Json<SuccessResponse<Vec<InstructionResponse>>>, ApiError> {
+    let mint = Pubkey::from_str(&payload.mint)
+        .map_err(|_| ApiError::InvalidPubkey(payload.mint.clone()))?;
+    let mint_authority = Pubkey::from_str(&payload.mint_authority)
+        .map_err(|_| ApiError::InvalidPubkey(payload.mint_authority.clone()))?;
+    let payer = Pubkey::from_str(&payload.payer)
+        .map_err(|_| ApiError::InvalidPubkey(payload.payer.clone()))?;
+    let owner = Pubkey::from_str(&payload.owner)
+        .map_err(|_| ApiError::InvalidPubkey(payload.owner.clone()))?;
+
+    let associated_token_account = get_associated_token_address(&owner, &mint);
+    let (metadata, _) = find_metadata_pda(&mint);
+    let (master_edition, _) = find_master_edition_pda(&mint);
+
+    let initialize_mint_instruction =
+        build_create_token_instruction(&mint_authority, &mint, 0)?;
+
+    let create_ata_instruction =
+        spl_associated_token_account::instruction::create_associated_token_account(
+            &payer,
+            &owner,
+            &mint,
+            &spl_token::id(),
+        );
+
+    let mint_to_instruction = build_mint_token_instruction(
+        &mint,
+        &associated_token_account,
+        &mint_authority,
+        1,
+    )?;
+
+    let create_metadata_instruction = build_create_metadata_instruction(
+        &metadata,
+        &mint,
+        &mint_authority,
+        &payer,
+        &mint_authority,
+        payload.name,
+        payload.symbol,
+        payload.uri,
+        payload.seller_fee_basis_points,
+        payload.creators,
+    )?;
+
+    let create_master_edition_instruction = build_create_master_edition_instruction(
+        &master_edition,
+        &mint,
+        &mint_authority,
+        &mint_authority,
+        &payer,
+        &metadata,
+    );
+
+    let instructions = vec![
+        InstructionResponse::from(&initialize_mint_instruction),
+        InstructionResponse::from(&create_ata_instruction),
+        InstructionResponse::from(&mint_to_instruction),
+        InstructionResponse::from(&create_metadata_instruction),
+        InstructionResponse::from(&create_master_edition_instruction),
+    ];
+
+    Ok(Json(SuccessResponse::new(instructions)))
+}
@@ -0,0 +1,284 @@
+use axum::Json;
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    hash::Hash,
+    message::Message,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use std::str::FromStr;
+
+use crate::{
+    build_create_token_instruction, build_mint_token_instruction, build_send_token_instruction,
+    decode_signer_secret, ApiError, SuccessResponse,
+};
+
+const DEFAULT_CLUSTER_URL: &str = "https://api.devnet.solana.com";
+
+#[derive(Serialize)]
+struct JsonRpcRequest<'a, T> {
+    jsonrpc: &'a str,
+    id: u64,
+    method: &'a str,
+    params: T,
+}
+
+#[derive(Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<JsonRpcErrorBody>,
+}
+
+#[derive(Deserialize)]
+struct JsonRpcErrorBody {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct LatestBlockhashResult {
+    value: LatestBlockhashValue,
+}
+
+#[derive(Deserialize)]
+struct LatestBlockhashValue {
+    blockhash: String,
+}
+
+/// Thin JSON-RPC 2.0 client over the Solana cluster RPC API.
+pub(crate) struct RpcClient {
+    cluster_url: String,
+    http: reqwest::Client,
+}
+
+impl RpcClient {
+    fn new(cluster_url: Option<String>) -> Self {
+        Self {
+            cluster_url: cluster_url.unwrap_or_else(|| DEFAULT_CLUSTER_URL.to_string()),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    async fn call<P: Serialize, R: for<'de> Deserialize<'de>>(
+        &self,
+        method: &str,
+        params: P,
+    ) -> Result<R, ApiError> {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0",
+            id: 1,
+            method,
+            params,
+        };
+
+        let response: JsonRpcResponse<R> = self
+            .http
+            .post(&self.cluster_url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| ApiError::RpcError(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| ApiError::RpcError(e.to_string()))?;
+
+        if let Some(error) = response.error {
+            return Err(ApiError::RpcError(error.message));
+        }
+
+        response
+            .result
+            .ok_or_else(|| ApiError::RpcError("missing result in RPC response".to_string()))
+    }
+
+    async fn get_latest_blockhash(&self) -> Result<Hash, ApiError> {
+        let result: LatestBlockhashResult = self
+            .call(
+                "getLatestBlockhash",
+                vec![serde_json::json!({ "commitment": "confirmed" })],
+            )
+            .await?;
+
+        Hash::from_str(&result.value.blockhash)
+            .map_err(|_| ApiError::RpcError("invalid blockhash in RPC response".to_string()))
+    }
+
+    async fn send_transaction(&self, transaction: &Transaction) -> Result<String, ApiError> {
+        let wire_transaction = general_purpose::STANDARD.encode(
+            bincode::serialize(transaction)
+                .map_err(|e| ApiError::RpcError(e.to_string()))?,
+        );
+
+        self.call(
+            "sendTransaction",
+            (
+                wire_transaction,
+                serde_json::json!({ "encoding": "base64", "skipPreflight": false }),
+            ),
+        )
+        .await
+    }
+
+    async fn request_airdrop(&self, pubkey: &Pubkey, lamports: u64) -> Result<String, ApiError> {
+        self.call("requestAirdrop", (pubkey.to_string(), lamports))
+            .await
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct BroadcastResponse {
+    signature: String,
+}
+
+async fn broadcast_instruction(
+    cluster_url: Option<String>,
+    signer: &Keypair,
+    instruction: solana_sdk::instruction::Instruction,
+) -> Result<String, ApiError> {
+    let rpc = RpcClient::new(cluster_url);
+    let blockhash = rpc.get_latest_blockhash().await?;
+
+    let message = Message::new_with_blockhash(&[instruction], Some(&signer.pubkey()), &blockhash);
+
+    // Some instructions (e.g. `mint_to` with an `authority` distinct from the fee payer) require
+    // a signer other than `signer`. `Transaction::new`'s `sign()` panics in that case; `try_sign`
+    // returns an error instead, the same way `tx_builder::build_transaction` propagates signing
+    // failures via `try_partial_sign` rather than letting the SDK panic.
+    let mut transaction = Transaction::new_unsigned(message);
+    transaction
+        .try_sign(&[signer], blockhash)
+        .map_err(|e| ApiError::InvalidSecretKey(e.to_string()))?;
+
+    rpc.send_transaction(&transaction).await
+}
+
+#[derive(Deserialize)]
+pub(crate) struct BroadcastCreateTokenRequest {
+    mint_authority: String,
+    mint: String,
+    decimals: u8,
+    signer_secret: String,
+    cluster_url: Option<String>,
+}
+
+pub(crate) async fn broadcast_create_token(
+    Json(payload): Json<BroadcastCreateTokenRequest>,
+) -> Result<Json<SuccessResponse<BroadcastResponse>>, ApiError> {
+    let mint_authority = Pubkey::from_str(&payload.mint_authority)
+        .map_err(|_| ApiError::InvalidPubkey(payload.mint_authority.clone()))?;
+    let mint = Pubkey::from_str(&payload.mint)
+        .map_err(|_| ApiError::InvalidPubkey(payload.mint.clone()))?;
+    let signer = decode_signer_secret(&payload.signer_secret)?;
+
+    let instruction = build_create_token_instruction(&mint_authority, &mint, payload.decimals)?;
+    let signature = broadcast_instruction(payload.cluster_url, &signer, instruction).await?;
+
+    Ok(Json(SuccessResponse::new(BroadcastResponse { signature })))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct BroadcastMintTokenRequest {
+    mint: String,
+    destination: String,
+    authority: String,
+    amount: u64,
+    signer_secret: String,
+    cluster_url: Option<String>,
+}
+
+pub(crate) async fn broadcast_mint_token(
+    Json(payload): Json<BroadcastMintTokenRequest>,
+) -> Result<Json<SuccessResponse<BroadcastResponse>>, ApiError> {
+    let mint = Pubkey::from_str(&payload.mint)
+        .map_err(|_| ApiError::InvalidPubkey(payload.mint.clone()))?;
+    let destination = Pubkey::from_str(&payload.destination)
+        .map_err(|_| ApiError::InvalidPubkey(payload.destination.clone()))?;
+    let authority = Pubkey::from_str(&payload.authority)
+        .map_err(|_| ApiError::InvalidPubkey(payload.authority.clone()))?;
+    let signer = decode_signer_secret(&payload.signer_secret)?;
+
+    let instruction =
+        build_mint_token_instruction(&mint, &destination, &authority, payload.amount)?;
+    let signature = broadcast_instruction(payload.cluster_url, &signer, instruction).await?;
+
+    Ok(Json(SuccessResponse::new(BroadcastResponse { signature })))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct BroadcastSendSolRequest {
+    to: String,
+    lamports: u64,
+    signer_secret: String,
+    cluster_url: Option<String>,
+}
+
+pub(crate) async fn broadcast_send_sol(
+    Json(payload): Json<BroadcastSendSolRequest>,
+) -> Result<Json<SuccessResponse<BroadcastResponse>>, ApiError> {
+    if payload.lamports == 0 {
+        return Err(ApiError::InvalidAmount);
+    }
+
+    let to = Pubkey::from_str(&payload.to)
+        .map_err(|_| ApiError::InvalidPubkey(payload.to.clone()))?;
+    let signer = decode_signer_secret(&payload.signer_secret)?;
+
+    let instruction =
+        solana_sdk::system_instruction::transfer(&signer.pubkey(), &to, payload.lamports);
+    let signature = broadcast_instruction(payload.cluster_url, &signer, instruction).await?;
+
+    Ok(Json(SuccessResponse::new(BroadcastResponse { signature })))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct BroadcastSendTokenRequest {
+    destination: String,
+    mint: String,
+    amount: u64,
+    signer_secret: String,
+    cluster_url: Option<String>,
+}
+
+pub(crate) async fn broadcast_send_token(
+    Json(payload): Json<BroadcastSendTokenRequest>,
+) -> Result<Json<SuccessResponse<BroadcastResponse>>, ApiError> {
+    if payload.amount == 0 {
+        return Err(ApiError::InvalidAmount);
+    }
+
+    let _mint = Pubkey::from_str(&payload.mint)
+        .map_err(|_| ApiError::InvalidPubkey(payload.mint.clone()))?;
+    let destination = Pubkey::from_str(&payload.destination)
+        .map_err(|_| ApiError::InvalidPubkey(payload.destination.clone()))?;
+    let signer = decode_signer_secret(&payload.signer_secret)?;
+
+    let instruction = build_send_token_instruction(
+        &signer.pubkey(),
+        &destination,
+        &signer.pubkey(),
+        payload.amount,
+    )?;
+    let signature = broadcast_instruction(payload.cluster_url, &signer, instruction).await?;
+
+    Ok(Json(SuccessResponse::new(BroadcastResponse { signature })))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct AirdropRequest {
+    pubkey: String,
+    lamports: u64,
+    cluster_url: Option<String>,
+}
+
+pub(crate) async fn airdrop(
+    Json(payload): Json<AirdropRequest>,
+) -> Result<Json<SuccessResponse<BroadcastResponse>>, ApiError> {
+    let pubkey = Pubkey::from_str(&payload.pubkey)
+        .map_err(|_| ApiError::InvalidPubkey(payload.pubkey.clone()))?;
+
+    let rpc = RpcClient::new(payload.cluster_url);
+    let signature = rpc.request_airdrop(&pubkey, payload.lamports).await?;
+
+    Ok(Json(SuccessResponse::new(BroadcastResponse { signature })))
+}
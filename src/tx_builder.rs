@@ -0,0 +1,185 @@
+use axum::Json;
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    message::Message,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    system_instruction,
+    transaction::Transaction,
+};
+use std::str::FromStr;
+
+use crate::{
+    build_create_token_instruction, build_mint_token_instruction, build_send_token_instruction,
+    decode_signer_secret, ApiError, SuccessResponse,
+};
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum InstructionSpec {
+    CreateToken {
+        mint_authority: String,
+        mint: String,
+        decimals: u8,
+    },
+    MintToken {
+        mint: String,
+        destination: String,
+        authority: String,
+        amount: u64,
+    },
+    Transfer {
+        mint: String,
+        destination: String,
+        owner: String,
+        amount: u64,
+    },
+    SendSol {
+        from: String,
+        to: String,
+        lamports: u64,
+    },
+}
+
+fn parse_pubkey(value: &str) -> Result<Pubkey, ApiError> {
+    Pubkey::from_str(value).map_err(|_| ApiError::InvalidPubkey(value.to_string()))
+}
+
+fn build_instruction(spec: &InstructionSpec) -> Result<Instruction, ApiError> {
+    match spec {
+        InstructionSpec::CreateToken {
+            mint_authority,
+            mint,
+            decimals,
+        } => build_create_token_instruction(
+            &parse_pubkey(mint_authority)?,
+            &parse_pubkey(mint)?,
+            *decimals,
+        ),
+        InstructionSpec::MintToken {
+            mint,
+            destination,
+            authority,
+            amount,
+        } => build_mint_token_instruction(
+            &parse_pubkey(mint)?,
+            &parse_pubkey(destination)?,
+            &parse_pubkey(authority)?,
+            *amount,
+        ),
+        InstructionSpec::Transfer {
+            mint: _,
+            destination,
+            owner,
+            amount,
+        } => build_send_token_instruction(
+            &parse_pubkey(owner)?,
+            &parse_pubkey(destination)?,
+            &parse_pubkey(owner)?,
+            *amount,
+        ),
+        InstructionSpec::SendSol { from, to, lamports } => Ok(system_instruction::transfer(
+            &parse_pubkey(from)?,
+            &parse_pubkey(to)?,
+            *lamports,
+        )),
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct BuildTxRequest {
+    instructions: Vec<InstructionSpec>,
+    fee_payer: String,
+    recent_blockhash: Option<String>,
+    signer_secrets: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct BuildTxResponse {
+    message: String,
+    transaction: Option<String>,
+    missing_signatures: Vec<String>,
+}
+
+/// Assembles an ordered list of instruction specs into a single `Message`, and when signer
+/// secrets are supplied, partially signs the resulting transaction. This is the offline/multisig
+/// co-signing entry point: the same specs, fee payer and blockhash always yield the same message, so every
+/// required signer can independently call this route and sign that message. The route does not
+/// take a previously signed transaction: the returned transaction carries only the signatures of
+/// the secrets passed in this call, and combining signatures from several calls is not done here.
+pub(crate) async fn build_transaction(
+    Json(payload): Json<BuildTxRequest>,
+) -> Result<Json<SuccessResponse<BuildTxResponse>>, ApiError> {
+    if payload.instructions.is_empty() {
+        return Err(ApiError::MissingField("instructions".to_string()));
+    }
+
+    if payload.signer_secrets.is_some() && payload.recent_blockhash.is_none() {
+        // Blockhash is part of the signed message; a placeholder would tie the collected
+        // signatures to a hash that can never be "recent" and the transaction could never
+        // broadcast. Offline/multisig callers must supply a real one up front.
+        return Err(ApiError::MissingField("recent_blockhash".to_string()));
+    }
+
+    let fee_payer = parse_pubkey(&payload.fee_payer)?;
+    let instructions = payload
+        .instructions
+        .iter()
+        .map(build_instruction)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let recent_blockhash = match &payload.recent_blockhash {
+        Some(blockhash) => Hash::from_str(blockhash)
+            .map_err(|_| ApiError::ProgramError("invalid recent_blockhash".to_string()))?,
+        // No signers were supplied (this is an unsigned-message preview only), so a placeholder
+        // blockhash is fine — nothing gets signed over it here.
+        None => Hash::default(),
+    };
+
+    let message = Message::new_with_blockhash(&instructions, Some(&fee_payer), &recent_blockhash);
+    let message_b64 = general_purpose::STANDARD.encode(
+        bincode::serialize(&message).map_err(|e| ApiError::ProgramError(e.to_string()))?,
+    );
+
+    let required_signers = message.account_keys[..message.header.num_required_signatures as usize]
+        .to_vec();
+
+    let Some(signer_secrets) = payload.signer_secrets else {
+        return Ok(Json(SuccessResponse::new(BuildTxResponse {
+            message: message_b64,
+            transaction: None,
+            missing_signatures: required_signers.iter().map(|p| p.to_string()).collect(),
+        })));
+    };
+
+    let signers = signer_secrets
+        .iter()
+        .map(|secret| decode_signer_secret(secret))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut transaction = Transaction::new_unsigned(message.clone());
+    let signer_refs: Vec<&Keypair> = signers.iter().collect();
+    transaction
+        .try_partial_sign(&signer_refs, recent_blockhash)
+        .map_err(|e| ApiError::ProgramError(e.to_string()))?;
+
+    let missing_signatures = required_signers
+        .iter()
+        .zip(transaction.signatures.iter())
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(pubkey, _)| pubkey.to_string())
+        .collect();
+
+    let transaction_b64 = general_purpose::STANDARD.encode(
+        bincode::serialize(&transaction).map_err(|e| ApiError::ProgramError(e.to_string()))?,
+    );
+
+    Ok(Json(SuccessResponse::new(BuildTxResponse {
+        message: message_b64,
+        transaction: Some(transaction_b64),
+        missing_signatures,
+    })))
+}